@@ -1,16 +1,18 @@
 use anyhow::{anyhow, Context};
-use disbahn::database::Database;
+use disbahn::config::{Config, FeedConfig, DEFAULT_POLL_INTERVAL_SECS};
+use disbahn::storage::sqlite::SqliteStorage;
+use disbahn::storage::Storage;
 use disbahn::DisbahnClient;
 use log::error;
 use serenity::http::Http;
 use std::env;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::io;
+use tokio::sync::{watch, Mutex};
 
-const DAEMON_INTERVAL_SECS: i64 = 300;
-
-fn env_var(name: &str) -> anyhow::Result<String> {
-    env::var(name).with_context(|| format!("Unable to fetch environment variable {name}"))
+fn config_path() -> String {
+    env::var("CONFIG_PATH").unwrap_or_else(|_| "config.toml".to_string())
 }
 
 #[tokio::main]
@@ -40,36 +42,115 @@ async fn main() -> anyhow::Result<()> {
         return Err(anyhow!("too many arguments"));
     }
 
-    let database_url = env_var("DATABASE_URL")?;
-    let webhook_url = env_var("WEBHOOK_URL")?;
-    let feed_url = env_var("FEED_URL")?;
+    let config = Config::load(config_path())?;
 
-    let database = Database::connect(&database_url)?;
-    let http = Http::new("");
-    let webhook = http.get_webhook_from_url(&webhook_url).await.unwrap();
+    #[cfg(feature = "metrics")]
+    if let Some(metrics_addr) = &config.metrics_addr {
+        let metrics_addr = metrics_addr
+            .parse()
+            .with_context(|| format!("Invalid metrics_addr {metrics_addr:?}"))?;
+        tokio::spawn(async move {
+            if let Err(err) = disbahn::metrics::serve(metrics_addr).await {
+                error!("Metrics server exited: {err}");
+            }
+        });
+    }
 
-    let mut disbahn_client = DisbahnClient::new(database, webhook, http, feed_url);
+    let storage: Arc<Mutex<Box<dyn Storage>>> = Arc::new(Mutex::new(Box::new(
+        SqliteStorage::connect(&config.database_url)?,
+    )));
+    let http = Arc::new(Http::new(""));
+    let http_client = reqwest::Client::new();
 
-    if daemon {
-        loop {
-            let now = chrono::Utc::now().timestamp();
-            let sleep_secs = (now / DAEMON_INTERVAL_SECS + 1) * DAEMON_INTERVAL_SECS - now;
-            let sleep_duration = sleep_secs.try_into().expect("sleep_secs is negative");
-            let shutdown = tokio::select! {
-                result = wait_for_shutdown_signal() => {
-                    result.expect("error on waiting for shutdown signal"); true
-                },
-                _ = tokio::time::sleep(Duration::from_secs(sleep_duration)) => false,
-            };
-            if shutdown {
-                break Ok(());
+    let mut clients = Vec::with_capacity(config.feeds.len());
+    for feed in config.feeds {
+        let FeedConfig {
+            feed_url,
+            webhook_url,
+            poll_interval_secs,
+            embed_colour,
+            footer_text,
+            deletion_mode,
+        } = feed;
+
+        let webhook = match http.get_webhook_from_url(&webhook_url).await {
+            Ok(webhook) => webhook,
+            Err(err) => {
+                error!("Skipping feed {feed_url:?}: invalid webhook URL {webhook_url:?}: {err}");
+                continue;
             }
-            if let Err(err) = disbahn_client.refresh().await {
+        };
+        let poll_interval_secs = poll_interval_secs.unwrap_or(DEFAULT_POLL_INTERVAL_SECS);
+        let client = DisbahnClient::new(
+            Arc::clone(&storage),
+            webhook,
+            Arc::clone(&http),
+            http_client.clone(),
+            feed_url,
+            embed_colour,
+            footer_text,
+            deletion_mode,
+        );
+        clients.push((client, poll_interval_secs));
+    }
+
+    if daemon {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+        let mut tasks = Vec::with_capacity(clients.len());
+        for (client, poll_interval_secs) in clients {
+            let shutdown_rx = shutdown_rx.clone();
+            tasks.push(tokio::spawn(run_daemon_loop(
+                client,
+                poll_interval_secs,
+                shutdown_rx,
+            )));
+        }
+
+        wait_for_shutdown_signal()
+            .await
+            .expect("error on waiting for shutdown signal");
+        shutdown_tx.send(true).ok();
+
+        for task in tasks {
+            task.await.expect("daemon task panicked");
+        }
+
+        Ok(())
+    } else {
+        for (mut client, _) in clients {
+            if let Err(err) = client.refresh().await {
                 error!("{}", err)
             }
         }
-    } else {
-        disbahn_client.refresh().await
+        Ok(())
+    }
+}
+
+async fn run_daemon_loop(
+    mut client: DisbahnClient,
+    poll_interval_secs: u64,
+    mut shutdown_rx: watch::Receiver<bool>,
+) {
+    let poll_interval_secs: i64 = poll_interval_secs
+        .try_into()
+        .expect("poll_interval_secs out of range");
+    loop {
+        let now = chrono::Utc::now().timestamp();
+        let sleep_secs = (now / poll_interval_secs + 1) * poll_interval_secs - now;
+        let sleep_duration = sleep_secs.try_into().expect("sleep_secs is negative");
+
+        tokio::select! {
+            _ = shutdown_rx.changed() => break,
+            _ = tokio::time::sleep(Duration::from_secs(sleep_duration)) => {}
+        }
+        if *shutdown_rx.borrow() {
+            break;
+        }
+
+        if let Err(err) = client.refresh().await {
+            error!("{}", err)
+        }
     }
 }
 
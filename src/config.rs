@@ -0,0 +1,55 @@
+//! Loading of `config.toml`, which describes the set of feeds disbahn should follow and
+//! the Discord webhook each one is posted to.
+
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Deserialize)]
+pub struct Config {
+    /// Where to persist post/message bookkeeping. Interpreted by whichever storage
+    /// backend is enabled (a file path for SQLite, a connection URL for Postgres/Redis).
+    pub database_url: String,
+    pub feeds: Vec<FeedConfig>,
+    /// Address to bind the Prometheus `/metrics` endpoint to, e.g. `0.0.0.0:9898`.
+    /// Only used when disbahn is built with the `metrics` feature.
+    pub metrics_addr: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct FeedConfig {
+    pub feed_url: String,
+    pub webhook_url: String,
+    /// How often to poll this feed. Defaults to [`crate::config::DEFAULT_POLL_INTERVAL_SECS`].
+    pub poll_interval_secs: Option<u64>,
+    /// Overrides the embed colour that would otherwise be derived from the item's icon.
+    pub embed_colour: Option<u32>,
+    /// Overrides the default "Quelle: ..." footer text.
+    pub footer_text: Option<String>,
+    /// What to do with a message whose announcement has dropped out of the feed.
+    #[serde(default)]
+    pub deletion_mode: DeletionMode,
+}
+
+/// How to handle a Discord message whose announcement has left the feed.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionMode {
+    /// Delete the Discord message and its `posts` row outright.
+    #[default]
+    Delete,
+    /// Edit the message into a greyed-out "beendet" state instead of deleting it.
+    MarkEnded,
+}
+
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 300;
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| anyhow::format_err!("Unable to read config file {path:?}: {err}"))?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|err| anyhow::format_err!("Unable to parse config file {path:?}: {err}"))?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,157 @@
+//! A thin normalization layer on top of [`feed_rs`], which parses RSS 0.9x/1.0/2.0 and
+//! Atom through a single [`Feed`]/[`Entry`] model. This lets [`crate::DisbahnClient`]
+//! follow feeds regardless of which of those formats an operator publishes.
+
+use crate::storage::models::FeedCache;
+use anyhow::Context;
+pub use feed_rs::model::{Category, Entry, Feed};
+use reqwest::header::{HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+
+/// The result of a conditional feed fetch.
+pub enum FetchOutcome {
+    /// The server reported `304 Not Modified`; the caller can skip parsing entirely.
+    NotModified,
+    /// The feed was (re-)downloaded, together with the validators to cache for next time
+    /// and the size of the response body in bytes.
+    Modified(Box<Feed>, FeedCache, usize),
+}
+
+/// Fetches and parses a feed, sending along any cached `ETag`/`Last-Modified` validators
+/// as `If-None-Match`/`If-Modified-Since` so the server can reply with `304 Not Modified`
+/// instead of the full feed body.
+///
+/// `client` is expected to be reused across calls (e.g. one per [`crate::DisbahnClient`])
+/// rather than constructed per fetch, so connection pooling/TLS session resumption/DNS
+/// caching actually pay off across polls.
+pub async fn fetch_feed_conditional(
+    client: &reqwest::Client,
+    feed_url: &str,
+    cache: Option<&FeedCache>,
+) -> anyhow::Result<FetchOutcome> {
+    let mut request = client.get(feed_url);
+    if let Some(cache) = cache {
+        if let Some(etag) = &cache.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cache.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
+
+    let new_cache = FeedCache {
+        feed_url: feed_url.to_string(),
+        etag: header_str(&response, reqwest::header::ETAG),
+        last_modified: header_str(&response, LAST_MODIFIED),
+    };
+
+    let content = response
+        .error_for_status()
+        .with_context(|| "Feed server returned an error response")?
+        .bytes()
+        .await?;
+    let byte_len = content.len();
+    let feed = feed_rs::parser::parse(&content[..])?;
+    Ok(FetchOutcome::Modified(Box::new(feed), new_cache, byte_len))
+}
+
+fn header_str(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value: &HeaderValue| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Finds the category whose `domain` (RSS) / `scheme` (Atom) attribute equals `domain`.
+///
+/// RSS and Atom categories are both represented as [`Category`] by feed-rs, with the
+/// domain/scheme attribute unified under [`Category::scheme`].
+pub fn category_by_domain<'a>(categories: &'a [Category], domain: &str) -> Option<&'a Category> {
+    categories
+        .iter()
+        .find(|category| category.scheme.as_deref() == Some(domain))
+}
+
+/// The entry's publication date, preferring the RSS `pubDate`/Atom `<published>` value
+/// and falling back to Atom's `<updated>` when no publication date was given.
+pub fn entry_published(entry: &Entry) -> Option<chrono::DateTime<chrono::Utc>> {
+    entry.published.or(entry.updated)
+}
+
+/// The entry's textual content, preferring the summary/description and falling back to
+/// the full content body.
+pub fn entry_description(entry: &Entry) -> Option<&str> {
+    entry
+        .summary
+        .as_ref()
+        .map(|text| text.content.as_str())
+        .or_else(|| entry.content.as_ref().and_then(|c| c.body.as_deref()))
+}
+
+/// The entry's human-facing URL, preferring a link with `rel` absent or `rel="alternate"`
+/// over e.g. Atom's `rel="self"` link (which points at the feed XML itself, not the page).
+pub fn entry_link(entry: &Entry) -> Option<&str> {
+    entry
+        .links
+        .iter()
+        .find(|link| matches!(link.rel.as_deref(), None | Some("alternate")))
+        .or_else(|| entry.links.first())
+        .map(|link| link.href.as_str())
+}
+
+pub fn entry_title(entry: &Entry) -> Option<&str> {
+    entry.title.as_ref().map(|text| text.content.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feed_rs::model::Link;
+
+    fn link(href: &str, rel: Option<&str>) -> Link {
+        Link {
+            href: href.to_string(),
+            rel: rel.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entry_link_prefers_alternate_over_self() {
+        let entry = Entry {
+            links: vec![
+                link("https://example.com/feed.xml", Some("self")),
+                link("https://example.com/article", Some("alternate")),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(entry_link(&entry), Some("https://example.com/article"));
+    }
+
+    #[test]
+    fn entry_link_accepts_a_link_with_no_rel() {
+        let entry = Entry {
+            links: vec![
+                link("https://example.com/feed.xml", Some("self")),
+                link("https://example.com/article", None),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(entry_link(&entry), Some("https://example.com/article"));
+    }
+
+    #[test]
+    fn entry_link_falls_back_to_first_link_when_no_alternate() {
+        let entry = Entry {
+            links: vec![link("https://example.com/feed.xml", Some("self"))],
+            ..Default::default()
+        };
+        assert_eq!(entry_link(&entry), Some("https://example.com/feed.xml"));
+    }
+}
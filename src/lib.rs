@@ -1,43 +1,107 @@
-pub mod database;
+pub mod config;
+pub mod feed;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod storage;
 
-use crate::database::models::{NewPost, Post};
-use crate::database::schema::posts::dsl::posts;
-use crate::database::Database;
+use crate::config::DeletionMode;
+use crate::feed::{Entry, Feed};
+use crate::storage::models::{NewPendingDelivery, NewPost, PendingDelivery};
+use crate::storage::Storage;
 use anyhow::{anyhow, Context};
-use chrono::{DateTime, NaiveDateTime, TimeZone};
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use chrono::{NaiveDateTime, Utc};
 use lazy_regex::regex;
-use log::{debug, error, info};
-use reqwest::IntoUrl;
-use rss::Item;
+use log::{debug, error, info, warn};
 use serenity::http::Http;
-use serenity::json::Value;
+use serenity::json::{from_str, to_string, Value};
 use serenity::model::channel::Embed;
+use serenity::model::id::MessageId;
 use serenity::model::webhook::Webhook;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// How many times a failed webhook delivery is retried before it is given up on.
+const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+/// Backoff before the first retry of a failed delivery.
+const INITIAL_BACKOFF_SECS: u64 = 30;
+/// Upper bound for the exponential backoff between retries.
+const MAX_BACKOFF_SECS: u64 = 3600;
+
+/// Exponential backoff for the `n`th delivery attempt, capped at [`MAX_BACKOFF_SECS`].
+fn backoff_delay(attempt_count: i32) -> Duration {
+    let secs = INITIAL_BACKOFF_SECS.saturating_mul(1u64 << attempt_count.clamp(0, 16));
+    Duration::from_secs(secs.min(MAX_BACKOFF_SECS))
+}
+
+/// How long to back off after a delivery fails with an HTTP 429. serenity's own `Http`
+/// client already retries ordinary rate limits internally using the real `Retry-After`
+/// header before ever returning an error to us, so by the time one reaches here the limit
+/// has outlasted serenity's own retry budget (e.g. a sustained global rate limit); the
+/// `serenity::Error` we get back doesn't carry the original response headers, so rather
+/// than guess a header value we don't have, we just wait the longest we'd ever wait anyway.
+const RATE_LIMIT_BACKOFF_SECS: u64 = MAX_BACKOFF_SECS;
+
+/// True if a failed delivery was rejected with HTTP 429 (rate limited), as opposed to some
+/// other Discord/network error.
+fn is_rate_limited(err: &serenity::Error) -> bool {
+    matches!(
+        err,
+        serenity::Error::Http(http_err)
+            if matches!(
+                http_err.as_ref(),
+                serenity::http::HttpError::UnsuccessfulRequest(response)
+                    if response.status_code == reqwest::StatusCode::TOO_MANY_REQUESTS
+            )
+    )
+}
+
+/// Delay before the next attempt: [`RATE_LIMIT_BACKOFF_SECS`] for a rate limit, otherwise
+/// the regular exponential schedule.
+fn next_attempt_delay(err: &serenity::Error, attempt_count: i32) -> Duration {
+    if is_rate_limited(err) {
+        Duration::from_secs(RATE_LIMIT_BACKOFF_SECS)
+    } else {
+        backoff_delay(attempt_count)
+    }
+}
 
 pub struct DisbahnClient {
-    database: Database,
+    storage: Arc<Mutex<Box<dyn Storage>>>,
     webhook: Webhook,
-    http: Http,
-    rss_url: String,
+    http: Arc<Http>,
+    http_client: reqwest::Client,
+    feed_url: String,
+    embed_colour: Option<u32>,
+    footer_text: Option<String>,
+    deletion_mode: DeletionMode,
 }
 
 impl DisbahnClient {
-    pub fn new(database: Database, webhook: Webhook, http: Http, rss_url: String) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        storage: Arc<Mutex<Box<dyn Storage>>>,
+        webhook: Webhook,
+        http: Arc<Http>,
+        http_client: reqwest::Client,
+        feed_url: String,
+        embed_colour: Option<u32>,
+        footer_text: Option<String>,
+        deletion_mode: DeletionMode,
+    ) -> Self {
         Self {
-            database,
+            storage,
             webhook,
             http,
-            rss_url,
+            http_client,
+            feed_url,
+            embed_colour,
+            footer_text,
+            deletion_mode,
         }
     }
 
-    async fn get_rss_channel<T: IntoUrl>(url: T) -> anyhow::Result<rss::Channel> {
-        let content = reqwest::get(url).await?.bytes().await?;
-        let channel = rss::Channel::read_from(&content[..])?;
-        Ok(channel)
-    }
-
     fn validity_time_to_timestamp(input: &str) -> anyhow::Result<i64> {
         let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S")?;
         let timestamp = chrono_tz::Europe::Berlin
@@ -78,141 +142,496 @@ impl DisbahnClient {
         }
     }
 
-    fn item_to_embed(item: &rss::Item) -> anyhow::Result<Value> {
+    fn item_to_embed(&self, entry: &Entry) -> anyhow::Result<Value> {
+        const DEFAULT_FOOTER_TEXT: &str =
+            "Quelle: https://zuginfo.nrw/ \u{2013} Alle Angaben ohne Gewehr \u{1F52B}";
         const FOOTER_ICON_URL: &str = "https://www.zuginfo.nrw/img/customer/apple-touch-icon.png";
 
-        let categories = item.categories();
-
-        let title = item.title().ok_or(anyhow!("Missing title"))?;
-        let link = item.link().ok_or(anyhow!("Missing link"))?;
-
-        let validity_begin = &categories
-            .iter()
-            .find(|c| c.domain() == Some("validityBegin"))
-            .ok_or(anyhow!("Missing validityBegin category"))?
-            .name;
-        let validity_begin = Self::validity_time_to_timestamp(validity_begin)?;
-
-        let validity_end = &categories
-            .iter()
-            .find(|c| c.domain() == Some("validityEnd"))
-            .ok_or(anyhow!("Missing validityEnd category"))?
-            .name;
-        let validity_end = Self::validity_time_to_timestamp(validity_end)?;
-
-        let icon = categories
-            .iter()
-            .find(|c| c.domain() == Some("icon"))
-            .map(|c| c.name())
+        let title = feed::entry_title(entry).ok_or(anyhow!("Missing title"))?;
+        let link = feed::entry_link(entry).ok_or(anyhow!("Missing link"))?;
+
+        let validity_begin = feed::category_by_domain(&entry.categories, "validityBegin")
+            .map(|c| Self::validity_time_to_timestamp(&c.term))
+            .transpose()?;
+
+        let validity_end = feed::category_by_domain(&entry.categories, "validityEnd")
+            .map(|c| Self::validity_time_to_timestamp(&c.term))
+            .transpose()?;
+
+        let icon = feed::category_by_domain(&entry.categories, "icon")
+            .map(|c| c.term.as_str())
             .unwrap_or("");
         let icon_url = Self::icon_name_to_url(icon);
 
         let description = Self::html_to_discord_markdown(
-            item.description().ok_or(anyhow!("Missing description"))?,
+            feed::entry_description(entry).ok_or(anyhow!("Missing description"))?,
         );
 
-        let pub_date_str = item.pub_date().ok_or(anyhow!("Missing publication date"))?;
-        let pub_datetime = DateTime::parse_from_rfc2822(pub_date_str)
-            .with_context(|| format!("Unable to parse publication date string {pub_date_str:?}"))?
-            .naive_utc()
-            .and_utc();
-
+        let pub_datetime =
+            feed::entry_published(entry).ok_or(anyhow!("Missing publication date"))?;
         let pub_timestamp = pub_datetime.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
 
-        let embed =
-            Embed::fake(|e| {
-                e.title(title)
-            .url(link)
-            .thumbnail(icon_url)
-            .colour(Self::icon_name_to_colour(icon))
-            .description(description)
-            .field("Beginn:", format!("<t:{}:F>", validity_begin), true)
-            .field("Ende:", format!("<t:{}:F>", validity_end), true)
-            .field("Hinweis:", include_str!("hint.txt"), false)
-            .timestamp(pub_timestamp)
-            .footer(|f| {
-                f.text("Quelle: https://zuginfo.nrw/ \u{2013} Alle Angaben ohne Gewehr \u{1F52B}")
-                    .icon_url(FOOTER_ICON_URL)
-            })
-            });
+        let colour = self
+            .embed_colour
+            .unwrap_or_else(|| Self::icon_name_to_colour(icon));
+        let footer_text = self.footer_text.as_deref().unwrap_or(DEFAULT_FOOTER_TEXT);
+
+        let embed = Embed::fake(|e| {
+            e.title(title)
+                .url(link)
+                .thumbnail(icon_url)
+                .colour(colour)
+                .description(description);
+            if let Some(validity_begin) = validity_begin {
+                e.field("Beginn:", format!("<t:{}:F>", validity_begin), true);
+            }
+            if let Some(validity_end) = validity_end {
+                e.field("Ende:", format!("<t:{}:F>", validity_end), true);
+            }
+            e.field("Hinweis:", include_str!("hint.txt"), false)
+                .timestamp(pub_timestamp)
+                .footer(|f| f.text(footer_text).icon_url(FOOTER_ICON_URL))
+        });
 
         Ok(embed)
     }
 
     pub async fn refresh(&mut self) -> anyhow::Result<()> {
-        debug!("Refreshing RSS feed ...");
-        let channel = Self::get_rss_channel(&self.rss_url)
+        debug!("Refreshing feed ...");
+
+        self.drain_pending_deliveries().await;
+
+        let cached = self
+            .storage
+            .lock()
+            .await
+            .get_feed_cache(&self.feed_url)
             .await
-            .map_err(|e| anyhow!(e.to_string()))
-            .with_context(|| "Failed to get RSS channel")?;
+            .with_context(|| "Error loading feed cache from storage")?;
 
-        let items = channel.items();
+        let fetch_started_at = std::time::Instant::now();
+        let fetch_outcome =
+            feed::fetch_feed_conditional(&self.http_client, &self.feed_url, cached.as_ref())
+            .await
+            .with_context(|| "Failed to get feed")?;
+        #[cfg(feature = "metrics")]
+        {
+            let bytes = match &fetch_outcome {
+                feed::FetchOutcome::NotModified => 0,
+                feed::FetchOutcome::Modified(_, _, bytes) => *bytes,
+            };
+            metrics::record_feed_fetch(
+                &self.feed_url,
+                fetch_started_at.elapsed().as_secs_f64(),
+                bytes,
+            );
+        }
 
-        for item in items {
-            if let Err(err) = self.refresh_item(item).await {
+        let parsed_feed = match fetch_outcome {
+            feed::FetchOutcome::NotModified => {
+                debug!("Feed not modified since last fetch, skipping.");
+                #[cfg(feature = "metrics")]
+                metrics::record_refresh_success(&self.feed_url);
+                return Ok(());
+            }
+            feed::FetchOutcome::Modified(feed, new_cache, _) => {
+                self.storage
+                    .lock()
+                    .await
+                    .set_feed_cache(new_cache)
+                    .await
+                    .with_context(|| "Error storing feed cache")?;
+                feed
+            }
+        };
+
+        for entry in &parsed_feed.entries {
+            if let Err(err) = self.refresh_item(entry).await {
                 error!("Error refreshing item: {err}");
             }
         }
 
+        if let Err(err) = self.reconcile_deletions(&parsed_feed).await {
+            error!("Error reconciling deleted items: {err}");
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::record_refresh_success(&self.feed_url);
+
         debug!("Done.");
         Ok(())
     }
 
-    async fn refresh_item(&mut self, item: &Item) -> anyhow::Result<()> {
-        use crate::database::schema::posts::{self, dsl};
+    /// Removes or marks as ended any previously posted message whose announcement has
+    /// dropped out of the feed, so the channel only reflects currently-valid disruptions.
+    async fn reconcile_deletions(&mut self, feed: &Feed) -> anyhow::Result<()> {
+        let current_guids: HashSet<&str> = feed.entries.iter().map(|e| e.id.as_str()).collect();
 
-        let guid = item.guid().ok_or(anyhow!("Missing GUID"))?.value();
-        let pub_date_str = item.pub_date().ok_or(anyhow!("Missing publication date"))?;
-        let pub_datetime = DateTime::parse_from_rfc2822(pub_date_str)
-            .with_context(|| format!("Unable to parse publication date string {pub_date_str:?}"))?
-            .naive_utc()
-            .and_utc();
+        let stale_posts: Vec<_> = self
+            .storage
+            .lock()
+            .await
+            .posts_for_webhook(self.webhook.id)
+            .await
+            .with_context(|| "Error loading posts from storage")?
+            .into_iter()
+            .filter(|post| !current_guids.contains(post.announcement_id().as_str()))
+            .collect();
 
-        let existing_post: Option<Post> = posts
-            .filter(dsl::webhook_id.eq(i64::from_le_bytes(self.webhook.id.0.to_le_bytes())))
-            .filter(dsl::announcement_id.eq(guid))
-            .first(self.database.conn())
-            .optional()
-            .with_context(|| "Error loading posts from database")?;
+        for post in stale_posts {
+            if let Err(err) = self.remove_stale_post(&post).await {
+                error!(
+                    "Error removing stale post {}: {err}",
+                    post.announcement_id()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_stale_post(&mut self, post: &storage::models::Post) -> anyhow::Result<()> {
+        match self.deletion_mode {
+            DeletionMode::Delete => {
+                self.webhook
+                    .delete_message(&self.http, post.message_id())
+                    .await
+                    .with_context(|| "Failed to delete message")?;
+            }
+            DeletionMode::MarkEnded => {
+                let embed = Self::ended_embed(post.announcement_id());
+                self.webhook
+                    .edit_message(&self.http, post.message_id(), |w| w.embeds(vec![embed]))
+                    .await
+                    .with_context(|| "Failed to edit message")?;
+            }
+        }
+
+        self.storage
+            .lock()
+            .await
+            .delete_post(self.webhook.id, post.announcement_id())
+            .await
+            .with_context(|| "Error deleting post from storage")?;
+        info!(
+            "Removed stale item: {} ({:?})",
+            post.announcement_id(),
+            self.deletion_mode
+        );
+        Ok(())
+    }
+
+    /// A greyed-out placeholder embed for an announcement that has left the feed. We only
+    /// keep the GUID around, not the original content, so this can't reuse [`Self::item_to_embed`].
+    fn ended_embed(announcement_id: &str) -> Value {
+        Embed::fake(|e| {
+            e.title("Meldung beendet")
+                .description(format!("Diese Meldung ({announcement_id}) ist nicht mehr aktiv."))
+                .colour(0x6d6d6d)
+        })
+    }
+
+    async fn refresh_item(&mut self, entry: &Entry) -> anyhow::Result<()> {
+        let guid = &entry.id;
+        let pub_datetime =
+            feed::entry_published(entry).ok_or(anyhow!("Missing publication date"))?;
+
+        let existing_post = self
+            .storage
+            .lock()
+            .await
+            .get_post(self.webhook.id, guid)
+            .await
+            .with_context(|| "Error loading post from storage")?;
 
         if let Some(existing_post) = existing_post {
             if existing_post.last_updated().and_utc() < pub_datetime {
                 info!("Updated item: {guid}");
-                let embed = Self::item_to_embed(item)?;
-                self.webhook
+                let embed = self.item_to_embed(entry)?;
+                let result = self
+                    .webhook
                     .edit_message(&self.http, existing_post.message_id(), |w| {
-                        w.embeds(vec![embed])
+                        w.embeds(vec![embed.clone()])
                     })
-                    .await
-                    .with_context(|| "Failed to edit message")?;
+                    .await;
 
-                diesel::update(
-                    posts.find((guid, i64::from_le_bytes(self.webhook.id.0.to_le_bytes()))),
-                )
-                .set(dsl::last_updated.eq(pub_datetime.naive_utc()))
-                .execute(self.database.conn())
-                .with_context(|| "Error updating post in database")?;
+                match result {
+                    Ok(_) => {
+                        self.storage
+                            .lock()
+                            .await
+                            .update_last_updated(self.webhook.id, guid, pub_datetime.naive_utc())
+                            .await
+                            .with_context(|| "Error updating post in storage")?;
+                        #[cfg(feature = "metrics")]
+                        metrics::record_item_updated(&self.feed_url);
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Failed to edit message for item {guid}, queuing for retry: {err}"
+                        );
+                        self.enqueue_retry(
+                            guid,
+                            Some(existing_post.message_id()),
+                            &embed,
+                            pub_datetime.naive_utc(),
+                            &err,
+                        )
+                        .await?;
+                    }
+                }
             }
         } else {
             info!("New item: {guid}");
-            let embed = Self::item_to_embed(item)?;
-            let message = self
+            let embed = self.item_to_embed(entry)?;
+            let result = self
+                .webhook
+                .execute(&self.http, true, |w| w.embeds(vec![embed.clone()]))
+                .await;
+
+            match result {
+                Ok(message) => {
+                    let message =
+                        message.with_context(|| "Discord did not return a message id")?;
+                    self.storage
+                        .lock()
+                        .await
+                        .upsert_post(NewPost::new(
+                            guid.clone(),
+                            self.webhook.id,
+                            message.id,
+                            pub_datetime.naive_utc(),
+                        ))
+                        .await
+                        .with_context(|| "Error inserting new post into storage")?;
+                    #[cfg(feature = "metrics")]
+                    metrics::record_item_posted(&self.feed_url);
+                }
+                Err(err) => {
+                    warn!("Failed to send message for item {guid}, queuing for retry: {err}");
+                    self.enqueue_retry(guid, None, &embed, pub_datetime.naive_utc(), &err)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues a failed webhook delivery for retry with exponential backoff, rather than
+    /// dropping the announcement on a transient Discord/network error.
+    async fn enqueue_retry(
+        &mut self,
+        announcement_id: &str,
+        message_id: Option<MessageId>,
+        embed: &Value,
+        pub_date: NaiveDateTime,
+        err: &serenity::Error,
+    ) -> anyhow::Result<()> {
+        #[cfg(feature = "metrics")]
+        metrics::record_delivery_failure(&self.feed_url);
+
+        let embed_json = to_string(embed).with_context(|| "Error serializing embed")?;
+        let next_attempt = Utc::now().naive_utc()
+            + chrono::Duration::from_std(next_attempt_delay(err, 0)).unwrap();
+
+        self.storage
+            .lock()
+            .await
+            .enqueue_pending_delivery(NewPendingDelivery::new(
+                self.webhook.id,
+                announcement_id,
+                message_id,
+                embed_json,
+                pub_date,
+                next_attempt,
+            ))
+            .await
+            .with_context(|| "Error queuing pending delivery in storage")?;
+        Ok(())
+    }
+
+    /// Retries any pending deliveries for this webhook that are due, so an earlier
+    /// Discord/network failure eventually catches up with the feed.
+    async fn drain_pending_deliveries(&mut self) {
+        let now = Utc::now().naive_utc();
+        let due = self
+            .storage
+            .lock()
+            .await
+            .due_pending_deliveries(self.webhook.id, now)
+            .await;
+
+        let due = match due {
+            Ok(due) => due,
+            Err(err) => {
+                error!("Error loading pending deliveries from storage: {err}");
+                return;
+            }
+        };
+
+        for job in due {
+            if let Err(err) = self.retry_pending_delivery(&job).await {
+                error!("Error retrying pending delivery {}: {err}", job.id());
+            }
+        }
+    }
+
+    async fn retry_pending_delivery(&mut self, job: &PendingDelivery) -> anyhow::Result<()> {
+        let embed: Value = from_str(job.embed_json())
+            .with_context(|| "Error deserializing queued embed")?;
+
+        if let Some(message_id) = job.message_id() {
+            let result = self
+                .webhook
+                .edit_message(&self.http, message_id, |w| w.embeds(vec![embed]))
+                .await;
+
+            match result {
+                Ok(_) => {
+                    self.storage
+                        .lock()
+                        .await
+                        .update_last_updated(self.webhook.id, job.announcement_id(), *job.pub_date())
+                        .await
+                        .with_context(|| "Error updating post in storage")?;
+                    self.delete_pending_delivery(job).await?;
+                }
+                Err(err) => {
+                    warn!(
+                        "Retry failed for pending delivery {} (edit): {err}",
+                        job.id()
+                    );
+                    self.fail_pending_delivery(job, &err).await?;
+                }
+            }
+        } else {
+            let result = self
                 .webhook
                 .execute(&self.http, true, |w| w.embeds(vec![embed]))
-                .await
-                .with_context(|| "Failed to send message")?
-                .with_context(|| "Discord did not return a message id")?;
-
-            diesel::insert_into(posts::table)
-                .values(NewPost::new(
-                    guid,
-                    self.webhook.id,
-                    message.id,
-                    pub_datetime.naive_utc(),
-                ))
-                .execute(self.database.conn())
-                .with_context(|| "Error inserting new post into database")?;
+                .await;
+
+            match result {
+                Ok(message) => {
+                    let message =
+                        message.with_context(|| "Discord did not return a message id")?;
+                    self.storage
+                        .lock()
+                        .await
+                        .upsert_post(NewPost::new(
+                            job.announcement_id().clone(),
+                            self.webhook.id,
+                            message.id,
+                            *job.pub_date(),
+                        ))
+                        .await
+                        .with_context(|| "Error inserting new post into storage")?;
+                    self.delete_pending_delivery(job).await?;
+                }
+                Err(err) => {
+                    warn!(
+                        "Retry failed for pending delivery {} (create): {err}",
+                        job.id()
+                    );
+                    self.fail_pending_delivery(job, &err).await?;
+                }
+            }
         }
         Ok(())
     }
+
+    async fn delete_pending_delivery(&mut self, job: &PendingDelivery) -> anyhow::Result<()> {
+        self.storage
+            .lock()
+            .await
+            .delete_pending_delivery(*job.id())
+            .await
+            .with_context(|| "Error deleting pending delivery from storage")
+    }
+
+    /// Bumps a failed retry's attempt count and reschedules it, or gives up once
+    /// [`MAX_DELIVERY_ATTEMPTS`] is reached.
+    async fn fail_pending_delivery(
+        &mut self,
+        job: &PendingDelivery,
+        err: &serenity::Error,
+    ) -> anyhow::Result<()> {
+        let attempt_count = *job.attempt_count() + 1;
+
+        if attempt_count >= MAX_DELIVERY_ATTEMPTS {
+            warn!(
+                "Giving up on pending delivery {} after {attempt_count} attempts",
+                job.id()
+            );
+            return self.delete_pending_delivery(job).await;
+        }
+
+        let next_attempt = Utc::now().naive_utc()
+            + chrono::Duration::from_std(next_attempt_delay(err, attempt_count)).unwrap();
+
+        self.storage
+            .lock()
+            .await
+            .record_delivery_attempt(*job.id(), attempt_count, next_attempt)
+            .await
+            .with_context(|| "Error recording pending delivery attempt in storage")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_delay_doubles_until_capped() {
+        assert_eq!(backoff_delay(0), Duration::from_secs(INITIAL_BACKOFF_SECS));
+        assert_eq!(backoff_delay(1), Duration::from_secs(INITIAL_BACKOFF_SECS * 2));
+        assert_eq!(backoff_delay(2), Duration::from_secs(INITIAL_BACKOFF_SECS * 4));
+        assert_eq!(backoff_delay(20), Duration::from_secs(MAX_BACKOFF_SECS));
+    }
+
+    fn rate_limited_error() -> serenity::Error {
+        serenity::Error::Http(Box::new(serenity::http::HttpError::UnsuccessfulRequest(
+            serenity::http::error::ErrorResponse {
+                status_code: reqwest::StatusCode::TOO_MANY_REQUESTS,
+                url: reqwest::Url::parse("https://discord.com/api/webhooks/1/abc").unwrap(),
+                error: serenity::http::error::DiscordJsonError {
+                    code: 0,
+                    message: "You are being rate limited.".to_string(),
+                    errors: vec![],
+                },
+            },
+        )))
+    }
+
+    fn not_found_error() -> serenity::Error {
+        serenity::Error::Http(Box::new(serenity::http::HttpError::UnsuccessfulRequest(
+            serenity::http::error::ErrorResponse {
+                status_code: reqwest::StatusCode::NOT_FOUND,
+                url: reqwest::Url::parse("https://discord.com/api/webhooks/1/abc").unwrap(),
+                error: serenity::http::error::DiscordJsonError {
+                    code: 10015,
+                    message: "Unknown Webhook".to_string(),
+                    errors: vec![],
+                },
+            },
+        )))
+    }
+
+    #[test]
+    fn is_rate_limited_detects_http_429() {
+        assert!(is_rate_limited(&rate_limited_error()));
+        assert!(!is_rate_limited(&not_found_error()));
+    }
+
+    #[test]
+    fn next_attempt_delay_uses_rate_limit_backoff_for_a_429() {
+        assert_eq!(
+            next_attempt_delay(&rate_limited_error(), 0),
+            Duration::from_secs(RATE_LIMIT_BACKOFF_SECS)
+        );
+    }
+
+    #[test]
+    fn next_attempt_delay_falls_back_to_backoff_schedule_otherwise() {
+        assert_eq!(next_attempt_delay(&not_found_error(), 1), backoff_delay(1));
+    }
 }
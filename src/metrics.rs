@@ -0,0 +1,108 @@
+//! Prometheus metrics for the daemon loop, exposed as a small HTTP endpoint so operators
+//! can alert on stalled or failing feeds (e.g. "feed hasn't refreshed successfully in N
+//! minutes").
+//!
+//! Metrics are registered into `prometheus`'s default registry and are global rather than
+//! threaded through [`crate::DisbahnClient`], the same way the `lazy_regex!` patterns in
+//! [`crate`] are shared process-wide statics.
+
+use anyhow::Context;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use lazy_static::lazy_static;
+use prometheus::{
+    register_gauge_vec, register_histogram_vec, register_int_counter_vec, Encoder, GaugeVec,
+    HistogramVec, IntCounterVec, TextEncoder,
+};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+lazy_static! {
+    static ref ITEMS_POSTED: IntCounterVec = register_int_counter_vec!(
+        "disbahn_items_posted_total",
+        "Number of new announcements posted to Discord",
+        &["feed_url"]
+    )
+    .unwrap();
+    static ref ITEMS_UPDATED: IntCounterVec = register_int_counter_vec!(
+        "disbahn_items_updated_total",
+        "Number of announcements whose Discord message was edited",
+        &["feed_url"]
+    )
+    .unwrap();
+    static ref DELIVERY_FAILURES: IntCounterVec = register_int_counter_vec!(
+        "disbahn_delivery_failures_total",
+        "Number of webhook deliveries that failed and were queued for retry",
+        &["feed_url"]
+    )
+    .unwrap();
+    static ref FETCH_DURATION: HistogramVec = register_histogram_vec!(
+        "disbahn_feed_fetch_duration_seconds",
+        "Time spent fetching and parsing a feed",
+        &["feed_url"]
+    )
+    .unwrap();
+    static ref FETCH_BYTES: HistogramVec = register_histogram_vec!(
+        "disbahn_feed_fetch_bytes",
+        "Size of a fetched feed response body",
+        &["feed_url"],
+        vec![1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0]
+    )
+    .unwrap();
+    static ref LAST_SUCCESS: GaugeVec = register_gauge_vec!(
+        "disbahn_last_successful_refresh_timestamp_seconds",
+        "Unix timestamp of the last successful refresh of a feed",
+        &["feed_url"]
+    )
+    .unwrap();
+}
+
+pub fn record_item_posted(feed_url: &str) {
+    ITEMS_POSTED.with_label_values(&[feed_url]).inc();
+}
+
+pub fn record_item_updated(feed_url: &str) {
+    ITEMS_UPDATED.with_label_values(&[feed_url]).inc();
+}
+
+pub fn record_delivery_failure(feed_url: &str) {
+    DELIVERY_FAILURES.with_label_values(&[feed_url]).inc();
+}
+
+pub fn record_feed_fetch(feed_url: &str, duration_secs: f64, bytes: usize) {
+    FETCH_DURATION
+        .with_label_values(&[feed_url])
+        .observe(duration_secs);
+    FETCH_BYTES
+        .with_label_values(&[feed_url])
+        .observe(bytes as f64);
+}
+
+pub fn record_refresh_success(feed_url: &str) {
+    LAST_SUCCESS
+        .with_label_values(&[feed_url])
+        .set(chrono::Utc::now().timestamp() as f64);
+}
+
+/// Serves the default Prometheus registry as text format on `GET /metrics` at `addr`.
+pub async fn serve(addr: SocketAddr) -> anyhow::Result<()> {
+    let make_svc = make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(handle)) });
+    Server::bind(&addr)
+        .serve(make_svc)
+        .await
+        .with_context(|| format!("Metrics server failed on {addr}"))
+}
+
+async fn handle(_req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let metric_families = prometheus::gather();
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(err) = encoder.encode(&metric_families, &mut buffer) {
+        log::error!("Error encoding metrics: {err}");
+        return Ok(Response::builder()
+            .status(500)
+            .body(Body::empty())
+            .unwrap());
+    }
+    Ok(Response::new(Body::from(buffer)))
+}
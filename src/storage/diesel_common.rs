@@ -0,0 +1,175 @@
+//! The diesel query logic shared by [`crate::storage::sqlite`] and
+//! [`crate::storage::postgres`]. Both backends use the same schema and the same queries;
+//! only the connection type differs, so [`impl_diesel_storage`] generates the
+//! [`Storage`](crate::storage::Storage) impl once and each backend module just invokes it
+//! for its own connection type.
+
+use serenity::model::id::WebhookId;
+
+pub(crate) fn to_i64(webhook_id: WebhookId) -> i64 {
+    i64::from_le_bytes(webhook_id.0.to_le_bytes())
+}
+
+/// Generates `impl Storage for $ty`, backed by a `conn` field of the connection type used
+/// by that `diesel::Connection` impl (`SqliteConnection`, `PgConnection`, ...).
+macro_rules! impl_diesel_storage {
+    ($ty:ty) => {
+        use anyhow::Context as _;
+        use chrono::NaiveDateTime;
+        use diesel::prelude::*;
+        use crate::storage::models::{FeedCache, NewPendingDelivery, NewPost, PendingDelivery, Post};
+        use serenity::model::id::WebhookId;
+
+        #[async_trait::async_trait]
+        impl crate::storage::Storage for $ty {
+            async fn get_post(
+                &mut self,
+                webhook_id: WebhookId,
+                announcement_id: &str,
+            ) -> anyhow::Result<Option<Post>> {
+                use crate::storage::schema::posts::dsl;
+                dsl::posts
+                    .filter(dsl::webhook_id.eq(crate::storage::diesel_common::to_i64(webhook_id)))
+                    .filter(dsl::announcement_id.eq(announcement_id))
+                    .first(&mut self.conn)
+                    .optional()
+                    .with_context(|| "Error loading post from database")
+            }
+
+            async fn upsert_post(&mut self, post: NewPost<'_>) -> anyhow::Result<()> {
+                use crate::storage::schema::posts;
+                diesel::insert_into(posts::table)
+                    .values(&post)
+                    .execute(&mut self.conn)
+                    .with_context(|| "Error inserting new post into database")?;
+                Ok(())
+            }
+
+            async fn update_last_updated(
+                &mut self,
+                webhook_id: WebhookId,
+                announcement_id: &str,
+                last_updated: NaiveDateTime,
+            ) -> anyhow::Result<()> {
+                use crate::storage::schema::posts::dsl;
+                diesel::update(dsl::posts.find((
+                    announcement_id,
+                    crate::storage::diesel_common::to_i64(webhook_id),
+                )))
+                .set(dsl::last_updated.eq(last_updated))
+                .execute(&mut self.conn)
+                .with_context(|| "Error updating post in database")?;
+                Ok(())
+            }
+
+            async fn posts_for_webhook(
+                &mut self,
+                webhook_id: WebhookId,
+            ) -> anyhow::Result<Vec<Post>> {
+                use crate::storage::schema::posts::dsl;
+                dsl::posts
+                    .filter(dsl::webhook_id.eq(crate::storage::diesel_common::to_i64(webhook_id)))
+                    .load(&mut self.conn)
+                    .with_context(|| "Error loading posts from database")
+            }
+
+            async fn delete_post(
+                &mut self,
+                webhook_id: WebhookId,
+                announcement_id: &str,
+            ) -> anyhow::Result<()> {
+                use crate::storage::schema::posts::dsl;
+                diesel::delete(dsl::posts.find((
+                    announcement_id,
+                    crate::storage::diesel_common::to_i64(webhook_id),
+                )))
+                .execute(&mut self.conn)
+                .with_context(|| "Error deleting post from database")?;
+                Ok(())
+            }
+
+            async fn get_feed_cache(
+                &mut self,
+                feed_url_value: &str,
+            ) -> anyhow::Result<Option<FeedCache>> {
+                use crate::storage::schema::feed_cache::dsl;
+                dsl::feed_cache
+                    .find(feed_url_value)
+                    .first(&mut self.conn)
+                    .optional()
+                    .with_context(|| "Error loading feed cache from database")
+            }
+
+            async fn set_feed_cache(&mut self, cache: FeedCache) -> anyhow::Result<()> {
+                use crate::storage::schema::feed_cache::{self, dsl};
+                let updated = diesel::update(dsl::feed_cache.find(&cache.feed_url))
+                    .set((
+                        dsl::etag.eq(&cache.etag),
+                        dsl::last_modified.eq(&cache.last_modified),
+                    ))
+                    .execute(&mut self.conn)
+                    .with_context(|| "Error updating feed cache in database")?;
+
+                if updated == 0 {
+                    diesel::insert_into(feed_cache::table)
+                        .values(&cache)
+                        .execute(&mut self.conn)
+                        .with_context(|| "Error inserting feed cache into database")?;
+                }
+                Ok(())
+            }
+
+            async fn enqueue_pending_delivery(
+                &mut self,
+                job: NewPendingDelivery,
+            ) -> anyhow::Result<()> {
+                use crate::storage::schema::pending_deliveries;
+                diesel::insert_into(pending_deliveries::table)
+                    .values(&job)
+                    .execute(&mut self.conn)
+                    .with_context(|| "Error inserting pending delivery into database")?;
+                Ok(())
+            }
+
+            async fn due_pending_deliveries(
+                &mut self,
+                webhook_id: WebhookId,
+                now: NaiveDateTime,
+            ) -> anyhow::Result<Vec<PendingDelivery>> {
+                use crate::storage::schema::pending_deliveries::dsl;
+                dsl::pending_deliveries
+                    .filter(dsl::webhook_id.eq(crate::storage::diesel_common::to_i64(webhook_id)))
+                    .filter(dsl::next_attempt.le(now))
+                    .load(&mut self.conn)
+                    .with_context(|| "Error loading pending deliveries from database")
+            }
+
+            async fn record_delivery_attempt(
+                &mut self,
+                id: i32,
+                attempt_count: i32,
+                next_attempt: NaiveDateTime,
+            ) -> anyhow::Result<()> {
+                use crate::storage::schema::pending_deliveries::dsl;
+                diesel::update(dsl::pending_deliveries.find(id))
+                    .set((
+                        dsl::attempt_count.eq(attempt_count),
+                        dsl::next_attempt.eq(next_attempt),
+                    ))
+                    .execute(&mut self.conn)
+                    .with_context(|| "Error updating pending delivery in database")?;
+                Ok(())
+            }
+
+            async fn delete_pending_delivery(&mut self, id: i32) -> anyhow::Result<()> {
+                use crate::storage::schema::pending_deliveries::dsl;
+                diesel::delete(dsl::pending_deliveries.find(id))
+                    .execute(&mut self.conn)
+                    .with_context(|| "Error deleting pending delivery from database")?;
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use impl_diesel_storage;
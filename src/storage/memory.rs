@@ -0,0 +1,302 @@
+use crate::storage::models::{FeedCache, NewPendingDelivery, NewPost, PendingDelivery, Post};
+use crate::storage::Storage;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serenity::model::id::WebhookId;
+use std::collections::HashMap;
+
+/// An in-memory [`Storage`] backend, mainly intended for tests and for running disbahn
+/// without any durable persistence.
+#[derive(Default)]
+pub struct MemoryStorage {
+    posts: HashMap<(WebhookId, String), Post>,
+    feed_caches: HashMap<String, FeedCache>,
+    pending_deliveries: HashMap<i32, PendingDelivery>,
+    next_pending_delivery_id: i32,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn get_post(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+    ) -> anyhow::Result<Option<Post>> {
+        Ok(self
+            .posts
+            .get(&(webhook_id, announcement_id.to_string()))
+            .cloned())
+    }
+
+    async fn upsert_post(&mut self, post: NewPost<'_>) -> anyhow::Result<()> {
+        let key = (post.webhook_id(), post.announcement_id().to_string());
+        let post = Post::new(
+            post.announcement_id().to_string(),
+            post.webhook_id(),
+            post.message_id(),
+            post.last_updated(),
+        );
+        self.posts.insert(key, post);
+        Ok(())
+    }
+
+    async fn update_last_updated(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+        last_updated: NaiveDateTime,
+    ) -> anyhow::Result<()> {
+        if let Some(post) = self
+            .posts
+            .get_mut(&(webhook_id, announcement_id.to_string()))
+        {
+            *post = Post::new(
+                post.announcement_id().to_string(),
+                webhook_id,
+                post.message_id(),
+                last_updated,
+            );
+        }
+        Ok(())
+    }
+
+    async fn posts_for_webhook(&mut self, webhook_id: WebhookId) -> anyhow::Result<Vec<Post>> {
+        Ok(self
+            .posts
+            .values()
+            .filter(|post| post.webhook_id() == webhook_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_post(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+    ) -> anyhow::Result<()> {
+        self.posts
+            .remove(&(webhook_id, announcement_id.to_string()));
+        Ok(())
+    }
+
+    async fn get_feed_cache(&mut self, feed_url: &str) -> anyhow::Result<Option<FeedCache>> {
+        Ok(self.feed_caches.get(feed_url).cloned())
+    }
+
+    async fn set_feed_cache(&mut self, cache: FeedCache) -> anyhow::Result<()> {
+        self.feed_caches.insert(cache.feed_url.clone(), cache);
+        Ok(())
+    }
+
+    async fn enqueue_pending_delivery(&mut self, job: NewPendingDelivery) -> anyhow::Result<()> {
+        let id = self.next_pending_delivery_id;
+        self.next_pending_delivery_id += 1;
+
+        self.pending_deliveries.insert(
+            id,
+            PendingDelivery::from_parts(
+                id,
+                job.webhook_id(),
+                job.announcement_id().to_string(),
+                job.message_id(),
+                job.embed_json().to_string(),
+                job.pub_date(),
+                0,
+                job.next_attempt(),
+            ),
+        );
+        Ok(())
+    }
+
+    async fn due_pending_deliveries(
+        &mut self,
+        webhook_id: WebhookId,
+        now: NaiveDateTime,
+    ) -> anyhow::Result<Vec<PendingDelivery>> {
+        Ok(self
+            .pending_deliveries
+            .values()
+            .filter(|job| job.webhook_id() == webhook_id && job.next_attempt() <= now)
+            .cloned()
+            .collect())
+    }
+
+    async fn record_delivery_attempt(
+        &mut self,
+        id: i32,
+        attempt_count: i32,
+        next_attempt: NaiveDateTime,
+    ) -> anyhow::Result<()> {
+        if let Some(job) = self.pending_deliveries.get_mut(&id) {
+            *job = PendingDelivery::from_parts(
+                id,
+                job.webhook_id(),
+                job.announcement_id().to_string(),
+                job.message_id(),
+                job.embed_json().to_string(),
+                job.pub_date(),
+                attempt_count,
+                next_attempt,
+            );
+        }
+        Ok(())
+    }
+
+    async fn delete_pending_delivery(&mut self, id: i32) -> anyhow::Result<()> {
+        self.pending_deliveries.remove(&id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::NewPost;
+    use serenity::model::id::MessageId;
+
+    fn webhook_id() -> WebhookId {
+        WebhookId(1)
+    }
+
+    #[tokio::test]
+    async fn upsert_then_get_post_roundtrips() {
+        let mut storage = MemoryStorage::new();
+        let now = chrono::Utc::now().naive_utc();
+        storage
+            .upsert_post(NewPost::new("guid-1", webhook_id(), MessageId(10), now))
+            .await
+            .unwrap();
+
+        let post = storage.get_post(webhook_id(), "guid-1").await.unwrap();
+        let post = post.expect("post should have been stored");
+        assert_eq!(post.announcement_id(), "guid-1");
+        assert_eq!(post.message_id(), MessageId(10));
+    }
+
+    #[tokio::test]
+    async fn posts_for_webhook_only_returns_matching_webhook() {
+        let mut storage = MemoryStorage::new();
+        let now = chrono::Utc::now().naive_utc();
+        storage
+            .upsert_post(NewPost::new("a", webhook_id(), MessageId(1), now))
+            .await
+            .unwrap();
+        storage
+            .upsert_post(NewPost::new("b", WebhookId(2), MessageId(2), now))
+            .await
+            .unwrap();
+
+        let posts = storage.posts_for_webhook(webhook_id()).await.unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].announcement_id(), "a");
+    }
+
+    #[tokio::test]
+    async fn delete_post_removes_it() {
+        let mut storage = MemoryStorage::new();
+        let now = chrono::Utc::now().naive_utc();
+        storage
+            .upsert_post(NewPost::new("a", webhook_id(), MessageId(1), now))
+            .await
+            .unwrap();
+
+        storage.delete_post(webhook_id(), "a").await.unwrap();
+
+        assert!(storage.get_post(webhook_id(), "a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn due_pending_deliveries_only_returns_jobs_past_next_attempt() {
+        let mut storage = MemoryStorage::new();
+        let now = chrono::Utc::now().naive_utc();
+        let past = now - chrono::Duration::seconds(60);
+        let future = now + chrono::Duration::seconds(60);
+
+        storage
+            .enqueue_pending_delivery(NewPendingDelivery::new(
+                webhook_id(),
+                "due",
+                None,
+                "{}",
+                now,
+                past,
+            ))
+            .await
+            .unwrap();
+        storage
+            .enqueue_pending_delivery(NewPendingDelivery::new(
+                webhook_id(),
+                "not-due",
+                None,
+                "{}",
+                now,
+                future,
+            ))
+            .await
+            .unwrap();
+
+        let due = storage.due_pending_deliveries(webhook_id(), now).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].announcement_id(), "due");
+    }
+
+    #[tokio::test]
+    async fn record_delivery_attempt_bumps_count_and_reschedules() {
+        let mut storage = MemoryStorage::new();
+        let now = chrono::Utc::now().naive_utc();
+        storage
+            .enqueue_pending_delivery(NewPendingDelivery::new(
+                webhook_id(),
+                "a",
+                None,
+                "{}",
+                now,
+                now,
+            ))
+            .await
+            .unwrap();
+        let job_id = *storage.due_pending_deliveries(webhook_id(), now).await.unwrap()[0].id();
+
+        let next = now + chrono::Duration::seconds(120);
+        storage
+            .record_delivery_attempt(job_id, 1, next)
+            .await
+            .unwrap();
+
+        let due = storage.due_pending_deliveries(webhook_id(), next).await.unwrap();
+        assert_eq!(*due[0].attempt_count(), 1);
+        assert_eq!(*due[0].next_attempt(), next);
+    }
+
+    #[tokio::test]
+    async fn delete_pending_delivery_removes_job() {
+        let mut storage = MemoryStorage::new();
+        let now = chrono::Utc::now().naive_utc();
+        storage
+            .enqueue_pending_delivery(NewPendingDelivery::new(
+                webhook_id(),
+                "a",
+                None,
+                "{}",
+                now,
+                now,
+            ))
+            .await
+            .unwrap();
+        let job_id = *storage.due_pending_deliveries(webhook_id(), now).await.unwrap()[0].id();
+
+        storage.delete_pending_delivery(job_id).await.unwrap();
+
+        assert!(storage
+            .due_pending_deliveries(webhook_id(), now)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
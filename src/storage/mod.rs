@@ -0,0 +1,86 @@
+pub mod models;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub mod schema;
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) mod diesel_common;
+#[cfg(feature = "memory")]
+pub mod memory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use crate::storage::models::{FeedCache, NewPendingDelivery, NewPost, PendingDelivery, Post};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serenity::model::id::WebhookId;
+
+/// A persistence backend for disbahn's announcement/message bookkeeping.
+///
+/// Implementations only need to support the handful of operations
+/// [`DisbahnClient::refresh_item`](crate::DisbahnClient) actually performs, which keeps
+/// adding a new backend (and swapping backends for tests) cheap.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Looks up the post recorded for `announcement_id` on `webhook_id`, if any.
+    async fn get_post(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+    ) -> anyhow::Result<Option<Post>>;
+
+    /// Records a newly posted announcement.
+    async fn upsert_post(&mut self, post: NewPost<'_>) -> anyhow::Result<()>;
+
+    /// Updates the `last_updated` timestamp of an already recorded post.
+    async fn update_last_updated(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+        last_updated: NaiveDateTime,
+    ) -> anyhow::Result<()>;
+
+    /// Returns every post recorded for `webhook_id`, so a reconciliation pass can spot
+    /// ones whose announcement has since left the feed.
+    async fn posts_for_webhook(&mut self, webhook_id: WebhookId) -> anyhow::Result<Vec<Post>>;
+
+    /// Removes a post, e.g. once its announcement has dropped out of the feed.
+    async fn delete_post(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+    ) -> anyhow::Result<()>;
+
+    /// Looks up the cached conditional-request validators (`ETag`/`Last-Modified`) for a
+    /// feed URL, if any were stored on a previous fetch.
+    async fn get_feed_cache(&mut self, feed_url: &str) -> anyhow::Result<Option<FeedCache>>;
+
+    /// Stores the conditional-request validators returned by a feed's server.
+    async fn set_feed_cache(&mut self, cache: FeedCache) -> anyhow::Result<()>;
+
+    /// Queues a webhook delivery that failed, so it can be retried later.
+    async fn enqueue_pending_delivery(&mut self, job: NewPendingDelivery) -> anyhow::Result<()>;
+
+    /// Returns the pending deliveries for `webhook_id` whose `next_attempt` has passed.
+    async fn due_pending_deliveries(
+        &mut self,
+        webhook_id: WebhookId,
+        now: NaiveDateTime,
+    ) -> anyhow::Result<Vec<PendingDelivery>>;
+
+    /// Records a further failed attempt, bumping the attempt count and scheduling the
+    /// next one.
+    async fn record_delivery_attempt(
+        &mut self,
+        id: i32,
+        attempt_count: i32,
+        next_attempt: NaiveDateTime,
+    ) -> anyhow::Result<()>;
+
+    /// Removes a pending delivery, either because it succeeded or because it was
+    /// abandoned after exceeding the maximum attempt count.
+    async fn delete_pending_delivery(&mut self, id: i32) -> anyhow::Result<()>;
+}
@@ -0,0 +1,246 @@
+use chrono::NaiveDateTime;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+use diesel::{Insertable, Queryable};
+use getset::Getters;
+use serenity::model::id::{MessageId, WebhookId};
+use std::borrow::Cow;
+
+#[derive(Clone, Getters)]
+#[cfg_attr(any(feature = "sqlite", feature = "postgres"), derive(Queryable))]
+#[cfg_attr(
+    any(feature = "sqlite", feature = "postgres"),
+    diesel(table_name = crate::storage::schema::posts)
+)]
+pub struct Post {
+    #[getset(get = "pub")]
+    announcement_id: String,
+    webhook_id: i64,
+    message_id: i64,
+    #[getset(get = "pub")]
+    last_updated: NaiveDateTime,
+}
+
+impl Post {
+    pub fn new(
+        announcement_id: impl Into<String>,
+        webhook_id: WebhookId,
+        message_id: MessageId,
+        last_updated: NaiveDateTime,
+    ) -> Self {
+        Self {
+            announcement_id: announcement_id.into(),
+            webhook_id: i64::from_le_bytes(webhook_id.0.to_le_bytes()),
+            message_id: i64::from_le_bytes(message_id.0.to_le_bytes()),
+            last_updated,
+        }
+    }
+
+    pub fn webhook_id(&self) -> WebhookId {
+        WebhookId(u64::from_le_bytes(self.webhook_id.to_le_bytes()))
+    }
+
+    pub fn message_id(&self) -> MessageId {
+        MessageId(u64::from_le_bytes(self.message_id.to_le_bytes()))
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(any(feature = "sqlite", feature = "postgres"), derive(Insertable))]
+#[cfg_attr(
+    any(feature = "sqlite", feature = "postgres"),
+    diesel(table_name = crate::storage::schema::posts)
+)]
+pub struct NewPost<'a> {
+    announcement_id: Cow<'a, str>,
+    webhook_id: i64,
+    message_id: i64,
+    last_updated: NaiveDateTime,
+}
+
+impl<'a> NewPost<'a> {
+    pub fn new(
+        announcement_id: impl Into<Cow<'a, str>>,
+        webhook_id: WebhookId,
+        message_id: MessageId,
+        last_updated: NaiveDateTime,
+    ) -> Self {
+        Self {
+            announcement_id: announcement_id.into(),
+            webhook_id: i64::from_le_bytes(webhook_id.0.to_le_bytes()),
+            message_id: i64::from_le_bytes(message_id.0.to_le_bytes()),
+            last_updated,
+        }
+    }
+
+    pub fn announcement_id(&self) -> &str {
+        &self.announcement_id
+    }
+
+    pub fn webhook_id(&self) -> WebhookId {
+        WebhookId(u64::from_le_bytes(self.webhook_id.to_le_bytes()))
+    }
+
+    pub fn message_id(&self) -> MessageId {
+        MessageId(u64::from_le_bytes(self.message_id.to_le_bytes()))
+    }
+
+    pub fn last_updated(&self) -> NaiveDateTime {
+        self.last_updated
+    }
+
+    pub fn into_owned(self) -> NewPost<'static> {
+        NewPost {
+            announcement_id: Cow::Owned(self.announcement_id.into_owned()),
+            webhook_id: self.webhook_id,
+            message_id: self.message_id,
+            last_updated: self.last_updated,
+        }
+    }
+}
+
+/// The conditional-request validators returned by a feed's server on the previous
+/// successful fetch, used to ask for a `304 Not Modified` on the next one.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    any(feature = "sqlite", feature = "postgres"),
+    derive(Queryable, Insertable)
+)]
+#[cfg_attr(
+    any(feature = "sqlite", feature = "postgres"),
+    diesel(table_name = crate::storage::schema::feed_cache)
+)]
+pub struct FeedCache {
+    pub feed_url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl FeedCache {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// A webhook delivery (new message or message edit) that failed and is waiting to be
+/// retried, so a transient Discord/network error doesn't drop the announcement.
+#[derive(Clone, Getters)]
+#[cfg_attr(any(feature = "sqlite", feature = "postgres"), derive(Queryable))]
+#[cfg_attr(
+    any(feature = "sqlite", feature = "postgres"),
+    diesel(table_name = crate::storage::schema::pending_deliveries)
+)]
+pub struct PendingDelivery {
+    #[getset(get = "pub")]
+    id: i32,
+    webhook_id: i64,
+    #[getset(get = "pub")]
+    announcement_id: String,
+    message_id: Option<i64>,
+    #[getset(get = "pub")]
+    embed_json: String,
+    #[getset(get = "pub")]
+    pub_date: NaiveDateTime,
+    #[getset(get = "pub")]
+    attempt_count: i32,
+    #[getset(get = "pub")]
+    next_attempt: NaiveDateTime,
+}
+
+impl PendingDelivery {
+    /// Builds a `PendingDelivery` from scalar values, for backends (e.g. Redis) that
+    /// don't get one for free via diesel's `Queryable`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_parts(
+        id: i32,
+        webhook_id: WebhookId,
+        announcement_id: String,
+        message_id: Option<MessageId>,
+        embed_json: String,
+        pub_date: NaiveDateTime,
+        attempt_count: i32,
+        next_attempt: NaiveDateTime,
+    ) -> Self {
+        Self {
+            id,
+            webhook_id: i64::from_le_bytes(webhook_id.0.to_le_bytes()),
+            announcement_id,
+            message_id: message_id.map(|id| i64::from_le_bytes(id.0.to_le_bytes())),
+            embed_json,
+            pub_date,
+            attempt_count,
+            next_attempt,
+        }
+    }
+
+    pub fn webhook_id(&self) -> WebhookId {
+        WebhookId(u64::from_le_bytes(self.webhook_id.to_le_bytes()))
+    }
+
+    /// `Some` if this job is an edit of an already-posted message, `None` if it is still
+    /// waiting to create the message in the first place.
+    pub fn message_id(&self) -> Option<MessageId> {
+        self.message_id
+            .map(|id| MessageId(u64::from_le_bytes(id.to_le_bytes())))
+    }
+}
+
+#[cfg_attr(any(feature = "sqlite", feature = "postgres"), derive(Insertable))]
+#[cfg_attr(
+    any(feature = "sqlite", feature = "postgres"),
+    diesel(table_name = crate::storage::schema::pending_deliveries)
+)]
+pub struct NewPendingDelivery {
+    webhook_id: i64,
+    announcement_id: String,
+    message_id: Option<i64>,
+    embed_json: String,
+    pub_date: NaiveDateTime,
+    attempt_count: i32,
+    next_attempt: NaiveDateTime,
+}
+
+impl NewPendingDelivery {
+    pub fn new(
+        webhook_id: WebhookId,
+        announcement_id: impl Into<String>,
+        message_id: Option<MessageId>,
+        embed_json: impl Into<String>,
+        pub_date: NaiveDateTime,
+        next_attempt: NaiveDateTime,
+    ) -> Self {
+        Self {
+            webhook_id: i64::from_le_bytes(webhook_id.0.to_le_bytes()),
+            announcement_id: announcement_id.into(),
+            message_id: message_id.map(|id| i64::from_le_bytes(id.0.to_le_bytes())),
+            embed_json: embed_json.into(),
+            pub_date,
+            attempt_count: 0,
+            next_attempt,
+        }
+    }
+
+    pub fn webhook_id(&self) -> WebhookId {
+        WebhookId(u64::from_le_bytes(self.webhook_id.to_le_bytes()))
+    }
+
+    pub fn announcement_id(&self) -> &str {
+        &self.announcement_id
+    }
+
+    pub fn message_id(&self) -> Option<MessageId> {
+        self.message_id
+            .map(|id| MessageId(u64::from_le_bytes(id.to_le_bytes())))
+    }
+
+    pub fn embed_json(&self) -> &str {
+        &self.embed_json
+    }
+
+    pub fn pub_date(&self) -> NaiveDateTime {
+        self.pub_date
+    }
+
+    pub fn next_attempt(&self) -> NaiveDateTime {
+        self.next_attempt
+    }
+}
@@ -0,0 +1,39 @@
+use crate::storage::diesel_common::impl_diesel_storage;
+use anyhow::format_err;
+use diesel::{Connection, PgConnection};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use log::debug;
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/postgres");
+
+/// A [`Storage`](crate::storage::Storage) backend persisting to a Postgres database via
+/// diesel.
+///
+/// Useful for operators who already run Postgres and would rather not stand up a second
+/// SQLite file alongside it.
+pub struct PostgresStorage {
+    conn: PgConnection,
+}
+
+impl PostgresStorage {
+    fn new(conn: PgConnection) -> Self {
+        Self { conn }
+    }
+
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        let mut connection = PgConnection::establish(url)
+            .map_err(|err| format_err!("Unable to connect to Postgres database at {url}: {err}"))?;
+        debug!("Established connection to Postgres database at {url}");
+
+        let migration_versions = connection
+            .run_pending_migrations(MIGRATIONS)
+            .map_err(|err| format_err!("Unable to run database migrations: {err}"))?;
+        if !migration_versions.is_empty() {
+            debug!("Ran database migrations for versions {migration_versions:?}");
+        }
+
+        Ok(Self::new(connection))
+    }
+}
+
+impl_diesel_storage!(PostgresStorage);
@@ -0,0 +1,367 @@
+use crate::storage::models::{FeedCache, NewPendingDelivery, NewPost, PendingDelivery, Post};
+use crate::storage::Storage;
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use redis::AsyncCommands;
+use serenity::model::id::WebhookId;
+
+/// A [`Storage`] backend persisting to Redis.
+///
+/// Posts are stored as hashes under `disbahn:post:{webhook_id}:{announcement_id}`, which
+/// keeps lookups a single `HGETALL` without needing a secondary index.
+pub struct RedisStorage {
+    client: redis::Client,
+}
+
+impl RedisStorage {
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)
+            .with_context(|| format!("Unable to create Redis client for {url}"))?;
+        Ok(Self { client })
+    }
+
+    fn key(webhook_id: WebhookId, announcement_id: &str) -> String {
+        format!("disbahn:post:{}:{announcement_id}", webhook_id.0)
+    }
+
+    /// A set of announcement ids posted under a webhook, so a reconciliation pass can
+    /// enumerate them without a Redis `KEYS` scan.
+    fn posts_index_key(webhook_id: WebhookId) -> String {
+        format!("disbahn:posts:{}", webhook_id.0)
+    }
+
+    fn feed_cache_key(feed_url: &str) -> String {
+        format!("disbahn:feed_cache:{feed_url}")
+    }
+
+    fn pending_delivery_key(id: i32) -> String {
+        format!("disbahn:pending_delivery:{id}")
+    }
+
+    /// A sorted set of pending delivery ids per webhook, scored by `next_attempt` so due
+    /// jobs can be fetched with a single `ZRANGEBYSCORE`.
+    fn pending_deliveries_index_key(webhook_id: WebhookId) -> String {
+        format!("disbahn:pending_deliveries:{}", webhook_id.0)
+    }
+
+    async fn connection(&self) -> anyhow::Result<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .with_context(|| "Unable to connect to Redis")
+    }
+}
+
+/// Converts a stored Unix timestamp back to a [`NaiveDateTime`].
+fn timestamp_to_naive(secs: i64) -> Option<NaiveDateTime> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|dt| dt.naive_utc())
+}
+
+#[async_trait]
+impl Storage for RedisStorage {
+    async fn get_post(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+    ) -> anyhow::Result<Option<Post>> {
+        let mut conn = self.connection().await?;
+        let key = Self::key(webhook_id, announcement_id);
+
+        let message_id: Option<i64> = conn
+            .hget(&key, "message_id")
+            .await
+            .with_context(|| "Error loading post from Redis")?;
+        let Some(message_id) = message_id else {
+            return Ok(None);
+        };
+        let last_updated: i64 = conn
+            .hget(&key, "last_updated")
+            .await
+            .with_context(|| "Error loading post from Redis")?;
+
+        Ok(Some(Post::new(
+            announcement_id,
+            webhook_id,
+            serenity::model::id::MessageId(u64::from_le_bytes(message_id.to_le_bytes())),
+            timestamp_to_naive(last_updated)
+                .with_context(|| "Stored last_updated timestamp is invalid")?,
+        )))
+    }
+
+    async fn upsert_post(&mut self, post: NewPost<'_>) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::key(post.webhook_id(), post.announcement_id());
+
+        let message_id = i64::from_le_bytes(post.message_id().0.to_le_bytes());
+        let last_updated = post.last_updated().and_utc().timestamp();
+
+        // Write the post hash and its index entry atomically, so a crash between the two
+        // can never leave an orphaned hash that posts_for_webhook will never see again.
+        redis::pipe()
+            .atomic()
+            .hset_multiple(&key, &[("message_id", message_id), ("last_updated", last_updated)])
+            .ignore()
+            .sadd(Self::posts_index_key(post.webhook_id()), post.announcement_id())
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .with_context(|| "Error writing post to Redis")?;
+        Ok(())
+    }
+
+    async fn update_last_updated(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+        last_updated: NaiveDateTime,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::key(webhook_id, announcement_id);
+
+        conn.hset::<_, _, _, ()>(&key, "last_updated", last_updated.and_utc().timestamp())
+            .await
+            .with_context(|| "Error updating post in Redis")?;
+        Ok(())
+    }
+
+    async fn posts_for_webhook(&mut self, webhook_id: WebhookId) -> anyhow::Result<Vec<Post>> {
+        let mut conn = self.connection().await?;
+
+        let announcement_ids: Vec<String> = conn
+            .smembers(Self::posts_index_key(webhook_id))
+            .await
+            .with_context(|| "Error loading post index from Redis")?;
+
+        let mut result = Vec::with_capacity(announcement_ids.len());
+        for announcement_id in announcement_ids {
+            if let Some(post) = self.get_post(webhook_id, &announcement_id).await? {
+                result.push(post);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn delete_post(
+        &mut self,
+        webhook_id: WebhookId,
+        announcement_id: &str,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::key(webhook_id, announcement_id);
+
+        redis::pipe()
+            .atomic()
+            .del(&key)
+            .ignore()
+            .srem(Self::posts_index_key(webhook_id), announcement_id)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .with_context(|| "Error deleting post from Redis")?;
+        Ok(())
+    }
+
+    async fn get_feed_cache(&mut self, feed_url: &str) -> anyhow::Result<Option<FeedCache>> {
+        let mut conn = self.connection().await?;
+        let key = Self::feed_cache_key(feed_url);
+
+        let (etag, last_modified): (Option<String>, Option<String>) = conn
+            .hget(&key, ("etag", "last_modified"))
+            .await
+            .with_context(|| "Error loading feed cache from Redis")?;
+
+        if etag.is_none() && last_modified.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(FeedCache {
+            feed_url: feed_url.to_string(),
+            etag,
+            last_modified,
+        }))
+    }
+
+    async fn set_feed_cache(&mut self, cache: FeedCache) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::feed_cache_key(&cache.feed_url);
+
+        for (field, value) in [("etag", cache.etag), ("last_modified", cache.last_modified)] {
+            match value {
+                Some(value) => {
+                    conn.hset::<_, _, _, ()>(&key, field, value)
+                        .await
+                        .with_context(|| "Error writing feed cache to Redis")?;
+                }
+                None => {
+                    conn.hdel::<_, _, ()>(&key, field)
+                        .await
+                        .with_context(|| "Error writing feed cache to Redis")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn enqueue_pending_delivery(&mut self, job: NewPendingDelivery) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+
+        let id: i32 = conn
+            .incr("disbahn:pending_delivery:next_id", 1)
+            .await
+            .with_context(|| "Error allocating pending delivery id in Redis")?;
+        let key = Self::pending_delivery_key(id);
+
+        redis::pipe()
+            .atomic()
+            .hset_multiple(
+                &key,
+                &[
+                    ("webhook_id", job.webhook_id().0.to_string()),
+                    ("announcement_id", job.announcement_id().to_string()),
+                    (
+                        "message_id",
+                        job.message_id().map(|id| id.0.to_string()).unwrap_or_default(),
+                    ),
+                    ("embed_json", job.embed_json().to_string()),
+                    ("pub_date", job.pub_date().and_utc().timestamp().to_string()),
+                    ("attempt_count", "0".to_string()),
+                    (
+                        "next_attempt",
+                        job.next_attempt().and_utc().timestamp().to_string(),
+                    ),
+                ],
+            )
+            .ignore()
+            .zadd(
+                Self::pending_deliveries_index_key(job.webhook_id()),
+                id,
+                job.next_attempt().and_utc().timestamp(),
+            )
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .with_context(|| "Error writing pending delivery to Redis")?;
+        Ok(())
+    }
+
+    async fn due_pending_deliveries(
+        &mut self,
+        webhook_id: WebhookId,
+        now: NaiveDateTime,
+    ) -> anyhow::Result<Vec<PendingDelivery>> {
+        let mut conn = self.connection().await?;
+
+        let ids: Vec<i32> = conn
+            .zrangebyscore(
+                Self::pending_deliveries_index_key(webhook_id),
+                i64::MIN,
+                now.and_utc().timestamp(),
+            )
+            .await
+            .with_context(|| "Error querying due pending deliveries from Redis")?;
+
+        let mut jobs = Vec::with_capacity(ids.len());
+        for id in ids {
+            let key = Self::pending_delivery_key(id);
+            let (announcement_id, message_id, embed_json, pub_date, attempt_count, next_attempt): (
+                String,
+                String,
+                String,
+                i64,
+                i32,
+                i64,
+            ) = conn
+                .hget(
+                    &key,
+                    (
+                        "announcement_id",
+                        "message_id",
+                        "embed_json",
+                        "pub_date",
+                        "attempt_count",
+                        "next_attempt",
+                    ),
+                )
+                .await
+                .with_context(|| "Error loading pending delivery from Redis")?;
+
+            jobs.push(PendingDelivery::from_parts(
+                id,
+                webhook_id,
+                announcement_id,
+                (!message_id.is_empty())
+                    .then(|| message_id.parse::<u64>())
+                    .transpose()
+                    .with_context(|| "Stored message_id is invalid")?
+                    .map(serenity::model::id::MessageId),
+                embed_json,
+                timestamp_to_naive(pub_date).with_context(|| "Stored pub_date is invalid")?,
+                attempt_count,
+                timestamp_to_naive(next_attempt)
+                    .with_context(|| "Stored next_attempt is invalid")?,
+            ));
+        }
+        Ok(jobs)
+    }
+
+    async fn record_delivery_attempt(
+        &mut self,
+        id: i32,
+        attempt_count: i32,
+        next_attempt: NaiveDateTime,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::pending_delivery_key(id);
+
+        let webhook_id: u64 = conn
+            .hget(&key, "webhook_id")
+            .await
+            .with_context(|| "Error loading pending delivery from Redis")?;
+
+        redis::pipe()
+            .atomic()
+            .hset_multiple(
+                &key,
+                &[
+                    ("attempt_count", attempt_count.to_string()),
+                    (
+                        "next_attempt",
+                        next_attempt.and_utc().timestamp().to_string(),
+                    ),
+                ],
+            )
+            .ignore()
+            .zadd(
+                Self::pending_deliveries_index_key(WebhookId(webhook_id)),
+                id,
+                next_attempt.and_utc().timestamp(),
+            )
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .with_context(|| "Error updating pending delivery in Redis")?;
+        Ok(())
+    }
+
+    async fn delete_pending_delivery(&mut self, id: i32) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::pending_delivery_key(id);
+
+        let webhook_id: u64 = conn
+            .hget(&key, "webhook_id")
+            .await
+            .with_context(|| "Error loading pending delivery from Redis")?;
+
+        redis::pipe()
+            .atomic()
+            .del(&key)
+            .ignore()
+            .zrem(Self::pending_deliveries_index_key(WebhookId(webhook_id)), id)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .with_context(|| "Error deleting pending delivery from Redis")?;
+        Ok(())
+    }
+}
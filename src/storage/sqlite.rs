@@ -1,19 +1,21 @@
-pub mod models;
-pub mod schema;
-
+use crate::storage::diesel_common::impl_diesel_storage;
 use anyhow::format_err;
-use diesel::prelude::*;
-use diesel::SqliteConnection;
+use diesel::{Connection, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use log::debug;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
-pub struct Database {
+/// A [`Storage`](crate::storage::Storage) backend persisting to a local SQLite database
+/// via diesel.
+///
+/// This is disbahn's original, zero-dependency-to-run-an-extra-service backend, and
+/// remains the default.
+pub struct SqliteStorage {
     conn: SqliteConnection,
 }
 
-impl Database {
+impl SqliteStorage {
     fn new(conn: SqliteConnection) -> Self {
         Self { conn }
     }
@@ -32,8 +34,6 @@ impl Database {
 
         Ok(Self::new(connection))
     }
-
-    pub fn conn(&mut self) -> &mut SqliteConnection {
-        &mut self.conn
-    }
 }
+
+impl_diesel_storage!(SqliteStorage);